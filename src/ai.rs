@@ -0,0 +1,286 @@
+//! Local search over beacon layouts, as an alternative to the greedy
+//! nearest-resource heuristic in `main`.
+
+use std::cmp::{Ordering, Reverse};
+use std::collections::BinaryHeap;
+use std::time::{Duration, Instant};
+
+use crate::{Action, ActionLine, CellKind, Game, Rng};
+
+/// How many layouts we keep around between perturbation rounds.
+const BEAM_WIDTH: usize = 4;
+
+/// How many turns the beam planner simulates ahead before judging a layout.
+const PLAN_HORIZON: usize = 6;
+
+fn clone_lines(lines: &[ActionLine]) -> Vec<ActionLine> {
+    lines
+        .iter()
+        .map(|line| ActionLine {
+            source: line.source,
+            destination: line.destination,
+            strength: line.strength,
+        })
+        .collect()
+}
+
+/// Simulate one turn with these lines as the allied beacons (the enemy is assumed to
+/// stand still) and score the result as banked crystals minus the enemy's.
+fn score(game: &Game, lines: &[ActionLine]) -> i32 {
+    let mut sim = game.clone();
+    let beacons = lines
+        .iter()
+        .flat_map(|line| {
+            sim.action_to_beacons(Action::Line(ActionLine {
+                source: line.source,
+                destination: line.destination,
+                strength: line.strength,
+            }))
+        })
+        .collect();
+    sim = sim.step(beacons, Vec::new());
+    sim.allied_score - sim.ennemy_score
+}
+
+/// Add/remove a beacon, or shift a beacon's strength.
+fn perturb(game: &Game, lines: &mut Vec<ActionLine>, rng: &mut Rng) {
+    let allied_base = game.allied_bases[0];
+    let resources = game.resource_cells();
+
+    match rng.next_below(3) {
+        0 if !lines.is_empty() => {
+            let index = rng.next_below(lines.len());
+            let delta = rng.next_below(21) as i32 - 10;
+            lines[index].strength = (lines[index].strength + delta).clamp(1, 100);
+        }
+        1 if !resources.is_empty() => {
+            let destination = resources[rng.next_below(resources.len())];
+            lines.push(ActionLine {
+                source: allied_base,
+                destination,
+                strength: 10,
+            });
+        }
+        2 if lines.len() > 1 => {
+            let index = rng.next_below(lines.len());
+            lines.remove(index);
+        }
+        _ => {}
+    }
+}
+
+/// Search for a good set of beacon-implying lines within `budget`: start from direct
+/// lines toward the closest eggs/crystals, then repeatedly perturb (add/remove a beacon,
+/// shift a strength) and keep the top `BEAM_WIDTH` layouts by simulated one-turn score.
+pub fn best_actions(game: &Game, budget: Duration) -> Vec<Action> {
+    let deadline = Instant::now() + budget;
+    let allied_base = game.allied_bases[0];
+
+    let mut seeds = Vec::new();
+    if let Some((_, eggs)) = game.closest_cell(allied_base, CellKind::Eggs) {
+        seeds.push(vec![ActionLine {
+            source: allied_base,
+            destination: eggs,
+            strength: 10,
+        }]);
+    }
+    if let Some((_, crystals)) = game.closest_cell(allied_base, CellKind::Crystals) {
+        seeds.push(vec![ActionLine {
+            source: allied_base,
+            destination: crystals,
+            strength: 10,
+        }]);
+    }
+    if seeds.is_empty() {
+        seeds.push(Vec::new());
+    }
+
+    let mut rng = Rng::new(0x9E3779B97F4A7C15);
+    let mut beam: Vec<(i32, Vec<ActionLine>)> = seeds
+        .into_iter()
+        .map(|lines| (score(game, &lines), lines))
+        .collect();
+    beam.sort_by_key(|x| Reverse(x.0));
+    beam.truncate(BEAM_WIDTH);
+
+    while Instant::now() < deadline {
+        let mut candidates = Vec::new();
+        for (_, lines) in &beam {
+            let mut candidate = clone_lines(lines);
+            perturb(game, &mut candidate, &mut rng);
+            let candidate_score = score(game, &candidate);
+            candidates.push((candidate_score, candidate));
+        }
+        beam.extend(candidates);
+        beam.sort_by_key(|x| Reverse(x.0));
+        beam.dedup_by(|a, b| a.1 == b.1);
+        beam.truncate(BEAM_WIDTH);
+    }
+
+    let best_lines = beam.into_iter().next().map(|(_, lines)| lines);
+    match best_lines {
+        Some(lines) if !lines.is_empty() => lines.into_iter().map(Action::Line).collect(),
+        _ => vec![Action::Wait],
+    }
+}
+
+/// A candidate layout together with its lookahead score, ordered by score so it can live
+/// in a `BinaryHeap` beam. Scores are never NaN in practice (they are sums of bounded game
+/// counters), so falling back to `Equal` on a `partial_cmp` miss is safe.
+struct ScoredLayout(f64, Vec<ActionLine>);
+
+impl PartialEq for ScoredLayout {
+    fn eq(&self, other: &Self) -> bool {
+        self.0 == other.0
+    }
+}
+impl Eq for ScoredLayout {}
+impl PartialOrd for ScoredLayout {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+impl Ord for ScoredLayout {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.0.partial_cmp(&other.0).unwrap_or(Ordering::Equal)
+    }
+}
+
+/// Simulate `horizon` turns with `lines` as a constant allied beacon layout (the enemy is
+/// assumed to stand still), and score the outcome: crystals banked, eggs converted to
+/// ants (weighted higher on the earlier turns, tapering to zero by the horizon), plus a
+/// small bonus for cells where the allied presence outnumbers the enemy's.
+fn evaluate(game: &Game, lines: &[ActionLine], horizon: usize) -> f64 {
+    let mut sim = game.clone();
+    let initial_score = sim.allied_score - sim.ennemy_score;
+
+    let mut eggs_score = 0.0;
+    for turn in 0..horizon {
+        let beacons = lines
+            .iter()
+            .flat_map(|line| {
+                sim.action_to_beacons(Action::Line(ActionLine {
+                    source: line.source,
+                    destination: line.destination,
+                    strength: line.strength,
+                }))
+            })
+            .collect();
+        let ants_before = sim.allied_ant_count();
+        sim = sim.step(beacons, Vec::new());
+        // movement is zero-sum and combat only removes ants, so any net gain here can only
+        // come from eggs hatching during this turn's harvest
+        let eggs_gained = (sim.allied_ant_count() - ants_before).max(0) as f64;
+        let weight = 1.0 - (turn as f64 / horizon as f64);
+        eggs_score += eggs_gained * weight;
+    }
+
+    let crystals_gained = (sim.allied_score - sim.ennemy_score - initial_score) as f64;
+    let contest_term = sim.contested_cells_won() as f64;
+    crystals_gained * 2.0 + eggs_score + contest_term * 0.1
+}
+
+/// How much a unit of pheromone counts toward a destination's score, relative to its raw
+/// resource count.
+const PHEROMONE_WEIGHT: f64 = 5.0;
+
+/// Score a resource cell as a beacon destination: not just by how much it currently holds,
+/// but by its pheromone trail too, so corridors that have repeatedly paid off get
+/// reinforced instead of the colony thrashing between equidistant targets.
+fn destination_score(game: &Game, cell: usize) -> f64 {
+    f64::from(game.resources_at(cell)) + PHEROMONE_WEIGHT * game.pheromone_at(cell)
+}
+
+/// Lookahead planner: start from an empty layout, and grow it one line at a time, keeping
+/// only the top `BEAM_WIDTH` layouts (by `evaluate`) at each expansion. Candidate lines
+/// all start at the allied base and reach toward a resource cell, mirroring how
+/// `beacons_of_line` turns a `Line` into beacons. After `PLAN_HORIZON` expansions (or one
+/// per candidate destination, if there are fewer), emit the best layout found as `Line`
+/// actions.
+pub fn plan(game: &Game) -> Vec<Action> {
+    let allied_base = game.allied_bases[0];
+    let mut destinations = game.resource_cells();
+    destinations.sort_by(|&a, &b| {
+        destination_score(game, b)
+            .partial_cmp(&destination_score(game, a))
+            .unwrap_or(Ordering::Equal)
+    });
+    destinations.truncate(8);
+    if destinations.is_empty() {
+        return vec![Action::Wait];
+    }
+
+    let mut beam = BinaryHeap::new();
+    beam.push(ScoredLayout(evaluate(game, &[], PLAN_HORIZON), Vec::new()));
+
+    for _ in 0..PLAN_HORIZON.min(destinations.len()) {
+        let mut expanded = BinaryHeap::new();
+        for ScoredLayout(score, lines) in beam.into_sorted_vec().into_iter().rev().take(BEAM_WIDTH)
+        {
+            // Keep the layout as-is, in case growing it further doesn't help.
+            expanded.push(ScoredLayout(score, clone_lines(&lines)));
+            for &destination in &destinations {
+                let mut candidate = clone_lines(&lines);
+                candidate.push(ActionLine {
+                    source: allied_base,
+                    destination,
+                    strength: 10,
+                });
+                let candidate_score = evaluate(game, &candidate, PLAN_HORIZON);
+                expanded.push(ScoredLayout(candidate_score, candidate));
+            }
+        }
+        beam = expanded;
+    }
+
+    match beam.into_iter().max() {
+        Some(ScoredLayout(_, lines)) if !lines.is_empty() => {
+            lines.into_iter().map(Action::Line).collect()
+        }
+        _ => vec![Action::Wait],
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::GenerationParams;
+
+    /// `evaluate` drives `Game::step` for the whole lookahead horizon, so this is also a
+    /// regression test for the `assign_moves`/`step` panic: `plan` must come back with at
+    /// least one action instead of crashing partway through the beam search.
+    #[test]
+    fn plan_runs_to_completion_on_a_generated_board() {
+        let params = GenerationParams {
+            radius: 3,
+            eggs_count: 2,
+            crystals_count: 2,
+            max_resources: 5,
+            starting_ants: 5,
+        };
+        let mut game = Game::generate(42, params);
+        game.init_topology();
+
+        let actions = plan(&game);
+        assert!(!actions.is_empty());
+    }
+
+    /// Same regression as `plan_runs_to_completion_on_a_generated_board`, but for the
+    /// `score`-driven beam/anneal search: it also drives `step` on every candidate, so it
+    /// panicked just as hard on the `assign_moves`/`step` index bug.
+    #[test]
+    fn best_actions_runs_to_completion_on_a_generated_board() {
+        let params = GenerationParams {
+            radius: 3,
+            eggs_count: 2,
+            crystals_count: 2,
+            max_resources: 5,
+            starting_ants: 5,
+        };
+        let mut game = Game::generate(43, params);
+        game.init_topology();
+
+        let actions = best_actions(&game, Duration::from_millis(10));
+        assert!(!actions.is_empty());
+    }
+}
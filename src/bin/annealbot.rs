@@ -0,0 +1,27 @@
+use std::time::Duration;
+
+use spring_challenge_2023::{ai, Game, PathMode};
+
+/// Search budget per turn: CodinGame's own per-turn time limit is 100ms, so leave headroom
+/// for I/O and the referee overhead.
+const TURN_BUDGET: Duration = Duration::from_millis(80);
+
+/// Same shape as `bot`, but driven by the beam/anneal search in `ai::best_actions` instead
+/// of the lookahead planner, so the referee can pit the two strategies against each other.
+fn main() {
+    let mut game = Game::parse().unwrap();
+    game.init_topology();
+    game.set_pathing_mode(PathMode::SafestRoute);
+
+    loop {
+        game = game.read_update().unwrap();
+
+        let actions = ai::best_actions(&game, TURN_BUDGET);
+        let line = actions
+            .iter()
+            .map(|action| action.to_string())
+            .collect::<Vec<_>>()
+            .join(";");
+        println!("{}", line);
+    }
+}
@@ -0,0 +1,17 @@
+use std::io::{self, BufRead};
+
+use spring_challenge_2023::console::GameConsole;
+
+fn main() {
+    let mut console = GameConsole::new();
+    for line in io::stdin().lock().lines() {
+        let line = line.expect("Failed to read command");
+        if line.trim().is_empty() {
+            continue;
+        }
+        match console.execute(&line) {
+            Ok(output) => println!("{}", output),
+            Err(error) => eprintln!("error: {:?}", error),
+        }
+    }
+}
@@ -1,6 +1,18 @@
+use std::io::{BufRead, BufReader, Write};
 use std::process::{Command, Stdio};
 
-use spring_challenge_2023::Game;
+use spring_challenge_2023::{parse_actions, Game};
+
+/// CodinGame caps a match at 100 turns; we mirror that here.
+const MAX_TURNS: usize = 100;
+
+fn read_bot_line<R: BufRead>(reader: &mut R) -> String {
+    let mut line = String::new();
+    reader
+        .read_line(&mut line)
+        .expect("Failed to read bot output");
+    line
+}
 
 fn main() {
     let args: Vec<_> = std::env::args().collect();
@@ -8,20 +20,60 @@ fn main() {
         println!("Usage: {} bot1 bot2", args[0]);
         return;
     }
-    let bot1 = Command::new(&args[1])
+
+    let mut bot1 = Command::new(&args[1])
         .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
         .spawn()
         .expect("Failed to start first bot");
-
-    let bot2 = Command::new(&args[2])
+    let mut bot2 = Command::new(&args[2])
         .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
         .spawn()
-        .expect("Failed to start first bot");
+        .expect("Failed to start second bot");
 
-    let mut bot1_input = &mut bot1.stdin.unwrap();
-    let mut bot2_input = &mut bot2.stdin.unwrap();
+    let mut bot1_input = bot1.stdin.take().unwrap();
+    let mut bot2_input = bot2.stdin.take().unwrap();
+    let mut bot1_output = BufReader::new(bot1.stdout.take().unwrap());
+    let mut bot2_output = BufReader::new(bot2.stdout.take().unwrap());
 
-    let game = Game::parse().unwrap();
+    let mut game = Game::parse().unwrap();
+    game.init_topology();
     game.write(&mut bot1_input);
     game.write(&mut bot2_input);
+
+    let win_threshold = game.total_crystals() / 2;
+
+    for turn in 0..MAX_TURNS {
+        game.write_update(&mut bot1_input);
+        game.write_update(&mut bot2_input);
+        bot1_input.flush().unwrap();
+        bot2_input.flush().unwrap();
+
+        // Reading order is fixed: bot1 (allied) always acts before bot2 (ennemy).
+        let allied_actions = parse_actions(&read_bot_line(&mut bot1_output)).unwrap();
+        let ennemy_actions = parse_actions(&read_bot_line(&mut bot2_output)).unwrap();
+
+        let allied_beacons = game.actions_to_beacons(allied_actions);
+        let ennemy_beacons = game.actions_to_beacons(ennemy_actions);
+
+        // `step` now resolves the whole turn: movement, then combat, then harvesting.
+        game = game.step(allied_beacons, ennemy_beacons);
+
+        eprintln!(
+            "turn {}: allied {} - {} ennemy",
+            turn + 1,
+            game.allied_score,
+            game.ennemy_score
+        );
+
+        if game.allied_score >= win_threshold || game.ennemy_score >= win_threshold {
+            break;
+        }
+    }
+
+    println!(
+        "Final score: {} - {}",
+        game.allied_score, game.ennemy_score
+    );
 }
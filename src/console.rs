@@ -0,0 +1,237 @@
+//! Offline replay/debug harness: reading only from stdin makes the bot impossible to test
+//! against a saved scenario, so this wraps `Game` behind a small Brigadier-style command
+//! dispatcher (literal commands registered once up front, then an input line is split and
+//! dispatched to the matching handler), letting a developer single-step and inspect
+//! planning on a fixed, captured game state.
+
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::BufReader;
+
+use crate::{Action, ActionLine, CellKind, Game, ParsingError, PathMode};
+
+type Handler = Box<dyn Fn(&mut ConsoleState, &[&str]) -> Result<String, ConsoleError>>;
+
+#[derive(Debug)]
+pub enum ConsoleError {
+    NoGameLoaded,
+    UnknownCommand(String),
+    WrongNumberOfArguments(&'static str),
+    InvalidArgument(String),
+    Parsing(ParsingError),
+    Io(std::io::Error),
+}
+
+/// Everything a registered command handler can see or mutate: the loaded game, and the
+/// beacon-implying actions queued by `line` for the next `step`.
+struct ConsoleState {
+    game: Option<Game>,
+    pending_actions: Vec<Action>,
+}
+
+/// Maps a literal command name to the handler that executes it, mirroring the
+/// register-then-dispatch shape of Mojang's Brigadier: commands are registered once, then
+/// `execute` splits an input line on whitespace and looks up the matching literal.
+struct CommandDispatcher {
+    commands: HashMap<&'static str, Handler>,
+}
+
+impl CommandDispatcher {
+    fn new() -> Self {
+        CommandDispatcher {
+            commands: HashMap::new(),
+        }
+    }
+
+    fn register(&mut self, literal: &'static str, handler: Handler) {
+        self.commands.insert(literal, handler);
+    }
+
+    fn execute(&self, state: &mut ConsoleState, input: &str) -> Result<String, ConsoleError> {
+        let mut tokens = input.split_whitespace();
+        let literal = tokens.next().ok_or(ConsoleError::WrongNumberOfArguments(""))?;
+        let args: Vec<&str> = tokens.collect();
+        let handler = self
+            .commands
+            .get(literal)
+            .ok_or_else(|| ConsoleError::UnknownCommand(literal.to_string()))?;
+        handler(state, &args)
+    }
+}
+
+fn loaded(state: &mut ConsoleState) -> Result<&mut Game, ConsoleError> {
+    state.game.as_mut().ok_or(ConsoleError::NoGameLoaded)
+}
+
+fn parse_cell(arg: &str) -> Result<usize, ConsoleError> {
+    arg.parse()
+        .map_err(|_| ConsoleError::InvalidArgument(arg.to_string()))
+}
+
+fn parse_cell_kind(arg: &str) -> Result<CellKind, ConsoleError> {
+    match arg {
+        "eggs" => Ok(CellKind::Eggs),
+        "crystals" => Ok(CellKind::Crystals),
+        _ => Err(ConsoleError::InvalidArgument(arg.to_string())),
+    }
+}
+
+fn parse_path_mode(arg: &str) -> Result<PathMode, ConsoleError> {
+    match arg {
+        "shortest" => Ok(PathMode::ShortestHop),
+        "safest" => Ok(PathMode::SafestRoute),
+        _ => Err(ConsoleError::InvalidArgument(arg.to_string())),
+    }
+}
+
+fn parse_line_args(args: &[&str]) -> Result<ActionLine, ConsoleError> {
+    let [source, destination, strength] = args else {
+        return Err(ConsoleError::WrongNumberOfArguments(
+            "<source> <destination> <strength>",
+        ));
+    };
+    Ok(ActionLine {
+        source: parse_cell(source)?,
+        destination: parse_cell(destination)?,
+        strength: strength
+            .parse()
+            .map_err(|_| ConsoleError::InvalidArgument(strength.to_string()))?,
+    })
+}
+
+/// A `Game` replay harness driven by single-line commands, for inspecting `ai`/`Game`
+/// behavior against a captured scenario instead of the live stdin protocol.
+pub struct GameConsole {
+    dispatcher: CommandDispatcher,
+    state: ConsoleState,
+}
+
+impl GameConsole {
+    pub fn new() -> Self {
+        let mut dispatcher = CommandDispatcher::new();
+
+        dispatcher.register(
+            "load",
+            Box::new(|state, args| {
+                let [path] = args else {
+                    return Err(ConsoleError::WrongNumberOfArguments("<file>"));
+                };
+                let mut reader = BufReader::new(File::open(path).map_err(ConsoleError::Io)?);
+                state.game = Some(Game::parse_from(&mut reader).map_err(ConsoleError::Parsing)?);
+                state.pending_actions.clear();
+                Ok(format!("loaded {}", path))
+            }),
+        );
+
+        dispatcher.register(
+            "update",
+            Box::new(|state, args| {
+                let [path] = args else {
+                    return Err(ConsoleError::WrongNumberOfArguments("<file>"));
+                };
+                let mut reader = BufReader::new(File::open(path).map_err(ConsoleError::Io)?);
+                let game = state.game.take().ok_or(ConsoleError::NoGameLoaded)?;
+                state.game = Some(
+                    game.read_update_from(&mut reader)
+                        .map_err(ConsoleError::Parsing)?,
+                );
+                Ok(format!("updated from {}", path))
+            }),
+        );
+
+        dispatcher.register(
+            "closest",
+            Box::new(|state, args| {
+                let [base, kind] = args else {
+                    return Err(ConsoleError::WrongNumberOfArguments("<base> eggs|crystals"));
+                };
+                let game = loaded(state)?;
+                let base = parse_cell(base)?;
+                let kind = parse_cell_kind(kind)?;
+                Ok(match game.closest_cell(base, kind) {
+                    Some((distance, cell)) => format!("{} at distance {}", cell, distance),
+                    None => "none".to_string(),
+                })
+            }),
+        );
+
+        dispatcher.register(
+            "mode",
+            Box::new(|state, args| {
+                let [mode] = args else {
+                    return Err(ConsoleError::WrongNumberOfArguments("shortest|safest"));
+                };
+                let mode = parse_path_mode(mode)?;
+                let game = loaded(state)?;
+                game.set_pathing_mode(mode);
+                Ok("ok".to_string())
+            }),
+        );
+
+        dispatcher.register(
+            "line",
+            Box::new(|state, args| {
+                let line = parse_line_args(args)?;
+                loaded(state)?;
+                state.pending_actions.push(Action::Line(line));
+                Ok("queued".to_string())
+            }),
+        );
+
+        dispatcher.register(
+            "beacons",
+            Box::new(|state, args| {
+                let line = parse_line_args(args)?;
+                let game = loaded(state)?;
+                let beacons = game.action_to_beacons(Action::Line(line));
+                Ok(beacons
+                    .iter()
+                    .map(|beacon| format!("{} {}", beacon.location, beacon.strength))
+                    .collect::<Vec<_>>()
+                    .join("\n"))
+            }),
+        );
+
+        dispatcher.register(
+            "step",
+            Box::new(|state, _args| {
+                let game = state.game.take().ok_or(ConsoleError::NoGameLoaded)?;
+                let actions = std::mem::take(&mut state.pending_actions);
+                let beacons = game.actions_to_beacons(actions);
+                let game = game.step(beacons, Vec::new());
+                let report = format!("allied {} - {} ennemy", game.allied_score, game.ennemy_score);
+                state.game = Some(game);
+                Ok(report)
+            }),
+        );
+
+        dispatcher.register(
+            "dump",
+            Box::new(|state, _args| {
+                let game = loaded(state)?;
+                let mut buffer = Vec::new();
+                game.write(&mut buffer);
+                Ok(String::from_utf8(buffer).unwrap())
+            }),
+        );
+
+        GameConsole {
+            dispatcher,
+            state: ConsoleState {
+                game: None,
+                pending_actions: Vec::new(),
+            },
+        }
+    }
+
+    /// Parse and dispatch one command line, returning what the handler printed.
+    pub fn execute(&mut self, input: &str) -> Result<String, ConsoleError> {
+        self.dispatcher.execute(&mut self.state, input)
+    }
+}
+
+impl Default for GameConsole {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -1,17 +1,22 @@
 use std::{
-    collections::{HashMap, HashSet, VecDeque},
+    cmp::Reverse,
+    collections::{BinaryHeap, HashMap, HashSet, VecDeque},
     convert::{TryFrom, TryInto},
     fmt::Display,
-    io::{self, Write},
+    io::{self, BufRead, Write},
     num::ParseIntError,
     str::FromStr,
 };
 
+pub mod ai;
+pub mod console;
+
 #[derive(Debug)]
 pub enum ParsingError {
     WrongNumberOfElements(String, usize, usize),
     NotAnInteger(String, ParseIntError),
     InvalidCellKind(i32),
+    InvalidAction(String),
     IoError(io::Error),
 }
 
@@ -27,9 +32,9 @@ fn parse_usize(s: &str) -> Result<usize, ParsingError> {
         .map_err(|e| ParsingError::NotAnInteger(s.to_string(), e))
 }
 
-fn next_line() -> Result<String, ParsingError> {
+fn next_line_from<R: BufRead>(reader: &mut R) -> Result<String, ParsingError> {
     let mut line = String::new();
-    match io::stdin().read_line(&mut line) {
+    match reader.read_line(&mut line) {
         Err(e) => Err(ParsingError::IoError(e)),
         Ok(_) => Ok(line),
     }
@@ -116,11 +121,69 @@ impl ToString for Cell {
     }
 }
 
+/// Which kind of path `beacons_of_line` (and friends) should compute.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum PathMode {
+    /// Plain BFS/matrix shortest path, ignoring who controls the cells along the way.
+    ShortestHop,
+    /// Dijkstra weighted to avoid cells where the enemy outnumbers us.
+    SafestRoute,
+}
+
+/// Tunable knobs for `Game::generate`.
+#[derive(Clone, Copy, Debug)]
+pub struct GenerationParams {
+    /// Number of hex rings around the center; the board is the hexagon of that radius.
+    pub radius: usize,
+    /// Number of mirrored egg deposits to scatter (so up to `2 * eggs_count` cells get eggs).
+    pub eggs_count: usize,
+    /// Number of mirrored crystal deposits to scatter.
+    pub crystals_count: usize,
+    /// Upper bound (inclusive) on the resources of any single deposit.
+    pub max_resources: i32,
+    /// Ants placed on each base at generation time, so the game is steppable immediately.
+    pub starting_ants: i32,
+}
+
+/// Small seeded xorshift64 generator: good enough to fuzz boards deterministically without
+/// pulling in a dependency.
+pub(crate) struct Rng(u64);
+
+impl Rng {
+    pub(crate) fn new(seed: u64) -> Self {
+        Rng(if seed == 0 { 0xdeadbeef } else { seed })
+    }
+
+    pub(crate) fn next_u64(&mut self) -> u64 {
+        let mut x = self.0;
+        x ^= x << 13;
+        x ^= x >> 7;
+        x ^= x << 17;
+        self.0 = x;
+        x
+    }
+
+    /// Uniform value in `0..bound`.
+    pub(crate) fn next_below(&mut self, bound: usize) -> usize {
+        (self.next_u64() as usize) % bound
+    }
+}
+
 #[derive(Clone, Debug)]
 pub struct Game {
     pub cells: Vec<Cell>,
     pub allied_bases: Vec<usize>,
     pub ennemy_bases: Vec<usize>,
+    pub allied_score: i32,
+    pub ennemy_score: i32,
+    // all-pairs shortest-path matrices, filled in by `init_topology`; `distances[i][j]` is
+    // the hop count from i to j, `next_hop[i][j]` the first step on that shortest path
+    distances: Option<Vec<Vec<u16>>>,
+    next_hop: Option<Vec<Vec<u32>>>,
+    // per-cell ant-colony-optimization trail, reinforced by `step` wherever a beacon
+    // actually harvested resources and evaporated every turn; one value per cell
+    pheromone: Vec<f64>,
+    pathing_mode: PathMode,
 }
 
 struct MoveAssignment {
@@ -129,6 +192,30 @@ struct MoveAssignment {
     amount: i32,
 }
 
+/// Which player's ants `assign_moves`/`apply_moves` is routing, so the same beacon-flow
+/// logic can drive either side's movement instead of being hardcoded to the allied one.
+#[derive(Clone, Copy)]
+enum Side {
+    Allied,
+    Ennemy,
+}
+
+impl Side {
+    fn ants(self, cell: &Cell) -> i32 {
+        match self {
+            Side::Allied => cell.allied_ants,
+            Side::Ennemy => cell.ennemy_ants,
+        }
+    }
+
+    fn ants_mut(self, cell: &mut Cell) -> &mut i32 {
+        match self {
+            Side::Allied => &mut cell.allied_ants,
+            Side::Ennemy => &mut cell.ennemy_ants,
+        }
+    }
+}
+
 impl Game {
     fn parse_bases(line: &str, count: usize) -> Result<Vec<usize>, ParsingError> {
         let ret = line
@@ -146,23 +233,199 @@ impl Game {
     }
 
     pub fn parse() -> Result<Game, ParsingError> {
-        let number_of_cells = parse_usize(&next_line()?)?;
+        Self::parse_from(&mut io::stdin().lock())
+    }
+
+    /// Same as `parse`, but reading from an arbitrary `BufRead` instead of stdin, so a
+    /// captured game dump can be replayed from a file (see `console::GameConsole`).
+    pub fn parse_from<R: BufRead>(reader: &mut R) -> Result<Game, ParsingError> {
+        let number_of_cells = parse_usize(&next_line_from(reader)?)?;
         let mut cells = Vec::new();
         for _ in 0..number_of_cells {
-            cells.push(next_line()?.parse()?);
+            cells.push(next_line_from(reader)?.parse()?);
         }
 
-        let number_of_bases = parse_usize(&next_line()?)?;
-        let allied_bases = Game::parse_bases(&next_line()?, number_of_bases)?;
-        let ennemy_bases = Game::parse_bases(&next_line()?, number_of_bases)?;
+        let number_of_bases = parse_usize(&next_line_from(reader)?)?;
+        let allied_bases = Game::parse_bases(&next_line_from(reader)?, number_of_bases)?;
+        let ennemy_bases = Game::parse_bases(&next_line_from(reader)?, number_of_bases)?;
 
+        let pheromone = vec![0.0; cells.len()];
         Ok(Game {
             cells,
             allied_bases,
             ennemy_bases,
+            allied_score: 0,
+            ennemy_score: 0,
+            distances: None,
+            next_hop: None,
+            pheromone,
+            pathing_mode: PathMode::ShortestHop,
         })
     }
 
+    /// Total crystals on the board, used by the referee to compute the win threshold
+    /// (a match ends once a player has banked half of this amount).
+    pub fn total_crystals(&self) -> i32 {
+        self.cells
+            .iter()
+            .filter(|cell| cell.kind == CellKind::Crystals)
+            .map(|cell| cell.resources)
+            .sum()
+    }
+
+    /// Build a valid board without a CodinGame seed: a hex cell graph with correct
+    /// 6-neighbor adjacency, one allied and one enemy base placed at point-symmetric
+    /// positions (so both players get an identical start), and eggs/crystals deposits
+    /// scattered with mirrored placement so the board stays fair. The result round-trips
+    /// cleanly through `write`/`parse`.
+    pub fn generate(seed: u64, params: GenerationParams) -> Game {
+        const AXIAL_DIRECTIONS: [(i32, i32); 6] =
+            [(1, 0), (1, -1), (0, -1), (-1, 0), (-1, 1), (0, 1)];
+        let radius = params.radius as i32;
+
+        // Hexagon of cells in axial coordinates, in a deterministic order.
+        let mut coords = Vec::new();
+        for q in -radius..=radius {
+            let r_min = (-radius).max(-q - radius);
+            let r_max = radius.min(-q + radius);
+            for r in r_min..=r_max {
+                coords.push((q, r));
+            }
+        }
+        let index_of: HashMap<(i32, i32), usize> = coords
+            .iter()
+            .enumerate()
+            .map(|(index, &coord)| (coord, index))
+            .collect();
+
+        let mut cells: Vec<Cell> = coords
+            .iter()
+            .map(|&(q, r)| {
+                let neighbors = AXIAL_DIRECTIONS
+                    .iter()
+                    .filter_map(|&(dq, dr)| index_of.get(&(q + dq, r + dr)).copied())
+                    .collect();
+                Cell {
+                    kind: CellKind::Empty,
+                    resources: 0,
+                    neighbors,
+                    allied_ants: 0,
+                    ennemy_ants: 0,
+                }
+            })
+            .collect();
+
+        // Point symmetry around the center puts the enemy base exactly opposite the
+        // allied one, with the rest of the board mirrored the same way.
+        let allied_base = index_of[&(radius, 0)];
+        let ennemy_base = index_of[&(-radius, 0)];
+        let mirror_of = |index: usize| -> usize {
+            let (q, r) = coords[index];
+            index_of[&(-q, -r)]
+        };
+
+        let mut rng = Rng::new(seed);
+        let mut candidates: Vec<usize> = (0..coords.len())
+            .filter(|&index| {
+                let (q, r) = coords[index];
+                (q > 0 || (q == 0 && r > 0)) && index != allied_base && index != ennemy_base
+            })
+            .collect();
+        // Fisher-Yates shuffle so the deposits drawn below are unbiased.
+        for i in (1..candidates.len()).rev() {
+            let j = rng.next_below(i + 1);
+            candidates.swap(i, j);
+        }
+
+        let mut place_deposits = |kind: CellKind, count: usize, rng: &mut Rng| {
+            for _ in 0..count {
+                let Some(index) = candidates.pop() else {
+                    break;
+                };
+                let resources = 1 + rng.next_below(params.max_resources.max(1) as usize) as i32;
+                cells[index].kind = kind;
+                cells[index].resources = resources;
+                let mirror = mirror_of(index);
+                cells[mirror].kind = kind;
+                cells[mirror].resources = resources;
+            }
+        };
+        place_deposits(CellKind::Eggs, params.eggs_count, &mut rng);
+        place_deposits(CellKind::Crystals, params.crystals_count, &mut rng);
+
+        cells[allied_base].allied_ants = params.starting_ants;
+        cells[ennemy_base].ennemy_ants = params.starting_ants;
+
+        let pheromone = vec![0.0; coords.len()];
+        let game = Game {
+            cells,
+            allied_bases: vec![allied_base],
+            ennemy_bases: vec![ennemy_base],
+            allied_score: 0,
+            ennemy_score: 0,
+            distances: None,
+            next_hop: None,
+            pheromone,
+            pathing_mode: PathMode::ShortestHop,
+        };
+        assert!(
+            game.is_connected(),
+            "generated board is not fully connected"
+        );
+        game
+    }
+
+    /// BFS reachability check from cell 0, reusing the same traversal as `closest_cell`,
+    /// used by `generate` to reject disconnected maps.
+    fn is_connected(&self) -> bool {
+        let mut visited = HashSet::new();
+        let mut q = VecDeque::new();
+        q.push_back(0);
+        visited.insert(0);
+        while let Some(state) = q.pop_front() {
+            for &neighbor in &self.cells[state].neighbors {
+                if visited.insert(neighbor) {
+                    q.push_back(neighbor);
+                }
+            }
+        }
+        visited.len() == self.cells.len()
+    }
+
+    /// Run a BFS from every cell to fill the all-pairs shortest-path matrices, and
+    /// remember them so `distance`/`path`/`assign_moves` no longer need to BFS on every
+    /// call. The `neighbors` adjacency is fixed for the whole match, so this only needs
+    /// to run once, right after `parse`.
+    pub fn init_topology(&mut self) {
+        let n = self.cells.len();
+        let mut distances = vec![vec![u16::MAX; n]; n];
+        let mut next_hop = vec![vec![u32::MAX; n]; n];
+        for source in 0..n {
+            distances[source][source] = 0;
+            let mut visited = vec![false; n];
+            visited[source] = true;
+            let mut q = VecDeque::new();
+            q.push_back(source);
+            while let Some(u) = q.pop_front() {
+                for &v in &self.cells[u].neighbors {
+                    if visited[v] {
+                        continue;
+                    }
+                    visited[v] = true;
+                    distances[source][v] = distances[source][u] + 1;
+                    next_hop[source][v] = if u == source {
+                        v as u32
+                    } else {
+                        next_hop[source][u]
+                    };
+                    q.push_back(v);
+                }
+            }
+        }
+        self.distances = Some(distances);
+        self.next_hop = Some(next_hop);
+    }
+
     pub fn write_bases<T: Write>(writer: &mut T, bases: &Vec<usize>) {
         write!(
             writer,
@@ -186,9 +449,15 @@ impl Game {
         Self::write_bases(writer, &self.ennemy_bases);
     }
 
-    pub fn read_update(mut self) -> Result<Game, ParsingError> {
+    pub fn read_update(self) -> Result<Game, ParsingError> {
+        self.read_update_from(&mut io::stdin().lock())
+    }
+
+    /// Same as `read_update`, but reading from an arbitrary `BufRead` instead of stdin, so
+    /// a captured per-turn update can be replayed from a file (see `console::GameConsole`).
+    pub fn read_update_from<R: BufRead>(mut self, reader: &mut R) -> Result<Game, ParsingError> {
         for cell in self.cells.iter_mut() {
-            let line = next_line()?;
+            let line = next_line_from(reader)?;
             let inputs = line.split(" ").collect::<Vec<_>>();
             cell.resources = parse_i32(inputs[0])?;
             cell.allied_ants = parse_i32(inputs[1])?;
@@ -199,7 +468,7 @@ impl Game {
 
     pub fn write_update<T: Write>(&self, writer: &mut T) {
         for cell in self.cells.iter() {
-            write!(
+            writeln!(
                 writer,
                 "{} {} {}",
                 cell.resources, cell.allied_ants, cell.ennemy_ants
@@ -209,6 +478,16 @@ impl Game {
     }
 
     fn path(&self, source: usize, destination: usize) -> Vec<usize> {
+        if let Some(next_hop) = &self.next_hop {
+            let mut path = vec![source];
+            let mut current = source;
+            while current != destination {
+                current = next_hop[current][destination] as usize;
+                path.push(current);
+            }
+            return path;
+        }
+
         let mut previous = HashMap::new();
         let mut q = VecDeque::new();
         q.push_back((0, source));
@@ -239,6 +518,10 @@ impl Game {
     }
 
     fn distance(&self, source: usize, destination: usize) -> usize {
+        if let Some(distances) = &self.distances {
+            return distances[source][destination] as usize;
+        }
+
         let mut visited = HashSet::new();
         let mut q = VecDeque::new();
         q.push_back((0, source));
@@ -260,6 +543,70 @@ impl Game {
         unreachable!();
     }
 
+    /// Cost of stepping onto `cell` for a `SafestRoute` path: 1 plus a penalty for every ant
+    /// by which the enemy outnumbers us there, so the weighted Dijkstra routes around
+    /// contested or enemy-held ground instead of walking straight through it.
+    fn safety_cost(&self, cell: usize) -> i32 {
+        const PENALTY: i32 = 10;
+        let cell = &self.cells[cell];
+        1 + PENALTY * (cell.ennemy_ants - cell.allied_ants).max(0)
+    }
+
+    /// Dijkstra shortest path weighted by `cost_fn(cell)`, the price of stepping onto each
+    /// cell reached (the source itself is free). Used by `SafestRoute` pathing; plain
+    /// unweighted routing stays on the BFS-backed `path`/`next_hop` matrix.
+    fn weighted_path(
+        &self,
+        source: usize,
+        destination: usize,
+        cost_fn: impl Fn(usize) -> i32,
+    ) -> Vec<usize> {
+        let mut best_cost = vec![i32::MAX; self.cells.len()];
+        let mut previous = vec![usize::MAX; self.cells.len()];
+        let mut heap = BinaryHeap::new();
+
+        best_cost[source] = 0;
+        heap.push(Reverse((0, source)));
+
+        while let Some(Reverse((cost, state))) = heap.pop() {
+            if state == destination {
+                break;
+            }
+            if cost > best_cost[state] {
+                continue;
+            }
+
+            let cell = &self.cells[state];
+            for &neighbor in &cell.neighbors {
+                let next_cost = cost + cost_fn(neighbor);
+                if next_cost < best_cost[neighbor] {
+                    best_cost[neighbor] = next_cost;
+                    previous[neighbor] = state;
+                    heap.push(Reverse((next_cost, neighbor)));
+                }
+            }
+        }
+
+        let mut path = vec![destination];
+        let mut current = destination;
+        while current != source {
+            current = previous[current];
+            path.push(current);
+        }
+        path.reverse();
+        path
+    }
+
+    /// Dispatch to the BFS shortest path or the safety-weighted one, per `mode`.
+    fn path_with_mode(&self, source: usize, destination: usize, mode: PathMode) -> Vec<usize> {
+        match mode {
+            PathMode::ShortestHop => self.path(source, destination),
+            PathMode::SafestRoute => {
+                self.weighted_path(source, destination, |cell| self.safety_cost(cell))
+            }
+        }
+    }
+
     pub fn closest_cell(&self, source: usize, target_kind: CellKind) -> Option<(usize, usize)> {
         let mut visited = HashSet::new();
         let mut q = VecDeque::new();
@@ -282,19 +629,199 @@ impl Game {
         None
     }
 
+    /// Cells currently holding eggs or crystals, for callers that want to enumerate
+    /// harvesting targets rather than walking toward a single closest one.
+    pub fn resource_cells(&self) -> Vec<usize> {
+        self.cells
+            .iter()
+            .enumerate()
+            .filter(|(_, cell)| cell.kind != CellKind::Empty && cell.resources > 0)
+            .map(|(index, _)| index)
+            .collect()
+    }
+
+    /// Choose whether `beacons_of_line` routes along the shortest path (the historical
+    /// behavior) or the safest one (avoiding cells where the enemy outnumbers us).
+    pub fn set_pathing_mode(&mut self, mode: PathMode) {
+        self.pathing_mode = mode;
+    }
+
+    /// Current resources left on `cell`, for callers scoring candidate harvest targets.
+    pub fn resources_at(&self, cell: usize) -> i32 {
+        self.cells[cell].resources
+    }
+
+    /// Current ant-colony-optimization pheromone trail on `cell`: higher means this cell
+    /// has repeatedly paid off as part of a harvesting beacon.
+    pub fn pheromone_at(&self, cell: usize) -> f64 {
+        self.pheromone[cell]
+    }
+
+    /// Total number of allied ants on the board, used by the planner to measure how many
+    /// ants an egg harvest actually produced.
+    pub fn allied_ant_count(&self) -> i32 {
+        self.cells.iter().map(|cell| cell.allied_ants).sum()
+    }
+
+    /// Number of cells where the allied presence outnumbers the enemy's, used by the
+    /// planner as a rough proxy for board control.
+    pub fn contested_cells_won(&self) -> i32 {
+        self.cells
+            .iter()
+            .filter(|cell| cell.allied_ants > cell.ennemy_ants)
+            .count() as i32
+    }
+
     fn beacons_of_line(&self, line: ActionLine) -> Vec<ActionBeacon> {
         let ActionLine {
             source,
             destination,
             strength,
         } = line;
-        self.path(source, destination)
+        self.path_with_mode(source, destination, self.pathing_mode)
             .iter()
             .map(|&location| ActionBeacon { location, strength })
             .collect()
     }
 
-    fn assign_moves(&self, beacons: Vec<ActionBeacon>) -> Vec<MoveAssignment> {
+    /// Turn a single turn's action into the beacons it implies, so the referee can feed
+    /// it straight into `step`/`assign_moves`. `Wait` and `ActionMessage` place no beacon.
+    pub fn action_to_beacons(&self, action: Action) -> Vec<ActionBeacon> {
+        match action {
+            Action::Wait => Vec::new(),
+            Action::Line(line) => self.beacons_of_line(line),
+            Action::Beacon(beacon) => vec![beacon],
+            Action::ActionMessage(_) => Vec::new(),
+        }
+    }
+
+    /// Same as `action_to_beacons`, but for a whole turn's worth of actions (a player may
+    /// submit several `LINE`/`BEACON` commands in a single turn).
+    pub fn actions_to_beacons(&self, actions: Vec<Action>) -> Vec<ActionBeacon> {
+        actions
+            .into_iter()
+            .flat_map(|action| self.action_to_beacons(action))
+            .collect()
+    }
+
+    /// For every cell, the strongest "attack chain" reaching it from `bases`: the maximum,
+    /// over all paths base->cell, of the minimum ant count (given by `ants_of`) along the
+    /// path. This is a widest-path search, i.e. Dijkstra with `min` relaxation and a
+    /// max-heap instead of the usual `+`/min-heap.
+    fn maximin_strength(&self, bases: &[usize], ants_of: impl Fn(&Cell) -> i32) -> Vec<i32> {
+        let mut best = vec![i32::MIN; self.cells.len()];
+        let mut heap = BinaryHeap::new();
+        for &base in bases {
+            let strength = ants_of(&self.cells[base]);
+            if strength > best[base] {
+                best[base] = strength;
+                heap.push((strength, base));
+            }
+        }
+
+        let mut unsettled_resource_cells = self
+            .cells
+            .iter()
+            .filter(|cell| cell.resources > 0)
+            .count();
+        while let Some((bottleneck, u)) = heap.pop() {
+            if bottleneck < best[u] {
+                continue; // stale entry, a better one was already settled
+            }
+            if self.cells[u].resources > 0 {
+                unsettled_resource_cells -= 1;
+                if unsettled_resource_cells == 0 {
+                    break;
+                }
+            }
+            for &v in &self.cells[u].neighbors {
+                let candidate = bottleneck.min(ants_of(&self.cells[v]));
+                if candidate > best[v] {
+                    best[v] = candidate;
+                    heap.push((candidate, v));
+                }
+            }
+        }
+        best
+    }
+
+    /// Contested cells (ants of both sides present) fight before resources are harvested:
+    /// the larger stack survives with the difference, ties wipe out both sides.
+    fn resolve_combat(&mut self) {
+        for cell in self.cells.iter_mut() {
+            match cell.allied_ants.cmp(&cell.ennemy_ants) {
+                std::cmp::Ordering::Equal if cell.allied_ants > 0 => {
+                    cell.allied_ants = 0;
+                    cell.ennemy_ants = 0;
+                }
+                std::cmp::Ordering::Greater if cell.ennemy_ants > 0 => {
+                    cell.allied_ants -= cell.ennemy_ants;
+                    cell.ennemy_ants = 0;
+                }
+                std::cmp::Ordering::Less if cell.allied_ants > 0 => {
+                    cell.ennemy_ants -= cell.allied_ants;
+                    cell.allied_ants = 0;
+                }
+                _ => {}
+            }
+        }
+    }
+
+    /// Official "attack chain" harvesting: a cell yields resources equal to the strongest
+    /// base-connected ant chain reaching it, capped by its remaining `resources`. Crystals
+    /// are banked into the owner's score, eggs grow the owner's ant pool on the spot.
+    /// Returns how much each cell yielded the allied side, so `step` can feed it to the
+    /// pheromone trail.
+    fn harvest(&mut self) -> Vec<i32> {
+        let allied_bases = self.allied_bases.clone();
+        let ennemy_bases = self.ennemy_bases.clone();
+        let allied_strength = self.maximin_strength(&allied_bases, |cell| cell.allied_ants);
+        let ennemy_strength = self.maximin_strength(&ennemy_bases, |cell| cell.ennemy_ants);
+
+        let mut allied_harvested = vec![0; self.cells.len()];
+        for (index, cell) in self.cells.iter_mut().enumerate() {
+            if cell.resources == 0 {
+                continue;
+            }
+            let allied_harvest = allied_strength[index].max(0).min(cell.resources);
+            let ennemy_harvest = ennemy_strength[index]
+                .max(0)
+                .min(cell.resources - allied_harvest);
+            allied_harvested[index] = allied_harvest;
+            cell.resources -= allied_harvest + ennemy_harvest;
+            match cell.kind {
+                CellKind::Eggs => {
+                    cell.allied_ants += allied_harvest;
+                    cell.ennemy_ants += ennemy_harvest;
+                }
+                CellKind::Crystals => {
+                    self.allied_score += allied_harvest;
+                    self.ennemy_score += ennemy_harvest;
+                }
+                CellKind::Empty => {}
+            }
+            if cell.resources == 0 {
+                cell.kind = CellKind::Empty;
+            }
+        }
+        allied_harvested
+    }
+
+    /// Reinforce the pheromone trail on every beacon cell that actually harvested
+    /// resources this turn (proportional to the amount collected), then evaporate the
+    /// whole trail by `rho` so stale corridors fade and the colony can commit to fresh,
+    /// repeatedly-productive ones instead of thrashing between equidistant targets.
+    fn deposit_pheromone(&mut self, beacon_locations: &[usize], allied_harvested: &[i32]) {
+        const RHO: f64 = 0.85;
+        for &location in beacon_locations {
+            self.pheromone[location] += f64::from(allied_harvested[location]);
+        }
+        for value in self.pheromone.iter_mut() {
+            *value *= RHO;
+        }
+    }
+
+    fn assign_moves(&self, side: Side, beacons: Vec<ActionBeacon>) -> Vec<MoveAssignment> {
         // sources (current ant positions)
         struct Source {
             location: usize,
@@ -302,14 +829,18 @@ impl Game {
         }
         let mut sources = Vec::new();
         for (index, cell) in self.cells.iter().enumerate() {
-            if cell.allied_ants != 0 {
+            let ants = side.ants(cell);
+            if ants == 0 {
                 continue;
             }
             sources.push(Source {
                 location: index,
-                ants: cell.allied_ants,
+                ants,
             })
         }
+        if sources.is_empty() {
+            return Vec::new();
+        }
 
         // sinks (beacons)
         struct Sink {
@@ -320,9 +851,7 @@ impl Game {
         let mut sinks = Vec::new();
         let scaling_factor = {
             let total_beacons: i32 = beacons.iter().map(|beacon| beacon.strength).sum();
-            // TODO: abstract allied_ants/ennemy_ants
-            let total_ants: i32 = self.cells.iter().map(|cell| cell.allied_ants).sum();
-            assert!(total_ants != 0);
+            let total_ants: i32 = self.cells.iter().map(|cell| side.ants(cell)).sum();
             f64::from(total_beacons) / f64::from(total_ants)
         };
         for beacon in &beacons {
@@ -361,8 +890,8 @@ impl Game {
                     continue;
                 }
                 assignments.push(MoveAssignment {
-                    source: source_index,
-                    destination: sink_index,
+                    source: source.location,
+                    destination: sink.location,
                     amount: assignment_size,
                 });
                 source.ants -= assignment_size;
@@ -371,7 +900,7 @@ impl Game {
             }
             pairs = pairs
                 .into_iter()
-                .filter(|&(_, source_index, _sink_index)| self.cells[source_index].allied_ants > 0)
+                .filter(|&(_, source_index, _sink_index)| sources[source_index].ants > 0)
                 .collect();
             stragglers = true;
         }
@@ -379,12 +908,12 @@ impl Game {
         assignments
     }
 
-    fn step(
-        mut self,
-        allied_beacons: Vec<ActionBeacon>,
-        _ennemy_beacons: Vec<ActionBeacon>,
-    ) -> Self {
-        let move_assignments = self.assign_moves(allied_beacons);
+    /// Move `side`'s ants one step along the path toward each of its beacon assignments,
+    /// per `assign_moves`. `assign_moves`'s rounded-up sink demand can ask for a few more
+    /// ants than a source cell actually has left, so the amount moved is capped at what's
+    /// there instead of assuming the assignment is always exact.
+    fn apply_moves(&mut self, side: Side, beacons: Vec<ActionBeacon>) {
+        let move_assignments = self.assign_moves(side, beacons);
         for move_assignment in move_assignments {
             let MoveAssignment {
                 source,
@@ -394,16 +923,28 @@ impl Game {
             let path = self.path(source, destination);
             if path.len() > 1 {
                 let source = &mut self.cells[source];
-                // TODO: abstract allied_ants/ennemy_ants
-                assert!(source.allied_ants >= amount);
-                source.allied_ants -= amount;
+                let amount = amount.min(*side.ants_mut(source));
+                *side.ants_mut(source) -= amount;
 
                 let next_step = path[1];
                 let next_step = &mut self.cells[next_step];
-                // TODO: abstract allied_ants/ennemy_ants
-                next_step.allied_ants += amount;
+                *side.ants_mut(next_step) += amount;
             }
         }
+    }
+
+    /// Simulate one full turn: movement resolution (both sides' ants flow toward their own
+    /// beacons), then combat resolution (contested cells fight), then resource resolution
+    /// (official attack-chain harvesting for both sides).
+    pub fn step(mut self, allied_beacons: Vec<ActionBeacon>, ennemy_beacons: Vec<ActionBeacon>) -> Self {
+        let beacon_locations: Vec<usize> =
+            allied_beacons.iter().map(|beacon| beacon.location).collect();
+        self.apply_moves(Side::Allied, allied_beacons);
+        self.apply_moves(Side::Ennemy, ennemy_beacons);
+
+        self.resolve_combat();
+        let allied_harvested = self.harvest();
+        self.deposit_pheromone(&beacon_locations, &allied_harvested);
         self
     }
 }
@@ -448,3 +989,238 @@ impl Display for Action {
         }
     }
 }
+
+impl FromStr for Action {
+    type Err = ParsingError;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut parts = s.trim_start().splitn(2, ' ');
+        let keyword = parts.next().unwrap_or("");
+        // `MESSAGE` keeps its remainder untouched below; every other command tolerates
+        // surrounding whitespace since its args get filtered of empty splits anyway.
+        let rest = parts.next().unwrap_or("");
+        match keyword {
+            "WAIT" => Ok(Action::Wait),
+            "LINE" => {
+                let args: Vec<&str> = rest.split(' ').filter(|arg| !arg.is_empty()).collect();
+                if args.len() != 3 {
+                    return Err(ParsingError::WrongNumberOfElements(
+                        s.to_string(),
+                        args.len(),
+                        3,
+                    ));
+                }
+                Ok(Action::Line(ActionLine {
+                    source: parse_usize(args[0])?,
+                    destination: parse_usize(args[1])?,
+                    strength: parse_i32(args[2])?,
+                }))
+            }
+            "BEACON" => {
+                let args: Vec<&str> = rest.split(' ').filter(|arg| !arg.is_empty()).collect();
+                if args.len() != 2 {
+                    return Err(ParsingError::WrongNumberOfElements(
+                        s.to_string(),
+                        args.len(),
+                        2,
+                    ));
+                }
+                Ok(Action::Beacon(ActionBeacon {
+                    location: parse_usize(args[0])?,
+                    strength: parse_i32(args[1])?,
+                }))
+            }
+            "MESSAGE" => Ok(Action::ActionMessage(ActionMessage {
+                message: rest.to_string(),
+            })),
+            _ => Err(ParsingError::InvalidAction(s.to_string())),
+        }
+    }
+}
+
+/// Parse a single turn's worth of bot output: CodinGame lets a player submit several
+/// `;`-separated commands per turn (typically one `LINE`/`BEACON` per allocation). A
+/// `MESSAGE` command's text is taken verbatim to the end of the line instead of being
+/// split further, since the free text it carries may itself contain `;` or spaces.
+pub fn parse_actions(line: &str) -> Result<Vec<Action>, ParsingError> {
+    let line = line.trim_end_matches(['\n', '\r']);
+    let mut actions = Vec::new();
+    let mut rest = line;
+    loop {
+        let keyword_end = rest.find([' ', ';']).unwrap_or(rest.len());
+        if &rest[..keyword_end] == "MESSAGE" {
+            actions.push(rest.parse()?);
+            break;
+        }
+        match rest.find(';') {
+            Some(index) => {
+                actions.push(rest[..index].parse()?);
+                rest = &rest[index + 1..];
+            }
+            None => {
+                actions.push(rest.parse()?);
+                break;
+            }
+        }
+    }
+    Ok(actions)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn make_cell(neighbors: Vec<usize>) -> Cell {
+        Cell {
+            kind: CellKind::Empty,
+            resources: 0,
+            neighbors,
+            allied_ants: 0,
+            ennemy_ants: 0,
+        }
+    }
+
+    /// Regression test for the `assign_moves`/`step` index mixup: stepping a freshly
+    /// generated, steppable board with a beacon line must move ants toward it instead of
+    /// panicking on an empty source cell.
+    #[test]
+    fn step_moves_ants_on_a_generated_board() {
+        let params = GenerationParams {
+            radius: 3,
+            eggs_count: 2,
+            crystals_count: 2,
+            max_resources: 5,
+            starting_ants: 5,
+        };
+        let mut game = Game::generate(42, params);
+        game.init_topology();
+
+        let allied_base = game.allied_bases[0];
+        let (_, target) = game
+            .closest_cell(allied_base, CellKind::Crystals)
+            .or_else(|| game.closest_cell(allied_base, CellKind::Eggs))
+            .expect("generated board should have resources");
+        let beacons = game.action_to_beacons(Action::Line(ActionLine {
+            source: allied_base,
+            destination: target,
+            strength: 10,
+        }));
+
+        let ants_before = game.allied_ant_count();
+        game = game.step(beacons, Vec::new());
+        // movement alone is zero-sum and only eggs hatching can add ants, so the total can
+        // only stay flat or grow
+        assert!(game.allied_ant_count() >= ants_before);
+        assert_ne!(game.cells[allied_base].allied_ants, ants_before);
+    }
+
+    /// Regression test for the missing enemy movement: `step` used to take
+    /// `ennemy_beacons` but silently ignore them, so the enemy base would have kept its
+    /// starting `5` ants forever instead of flowing them away.
+    #[test]
+    fn step_moves_ennemy_ants_too() {
+        let params = GenerationParams {
+            radius: 3,
+            eggs_count: 2,
+            crystals_count: 2,
+            max_resources: 5,
+            starting_ants: 5,
+        };
+        let mut game = Game::generate(44, params);
+        game.init_topology();
+
+        let ennemy_base = game.ennemy_bases[0];
+        let (_, target) = game
+            .closest_cell(ennemy_base, CellKind::Crystals)
+            .or_else(|| game.closest_cell(ennemy_base, CellKind::Eggs))
+            .expect("generated board should have resources");
+        let beacons = game.action_to_beacons(Action::Line(ActionLine {
+            source: ennemy_base,
+            destination: target,
+            strength: 10,
+        }));
+
+        game = game.step(Vec::new(), beacons);
+        assert_ne!(game.cells[ennemy_base].ennemy_ants, 5);
+    }
+
+    /// `deposit_pheromone` both reinforces beacon cells that just harvested and evaporates
+    /// the whole trail by `RHO`; a cell that harvests once then goes quiet should spike up
+    /// and then decay back down, not stay flat or keep climbing.
+    #[test]
+    fn pheromone_deposits_then_evaporates() {
+        let cells = vec![make_cell(vec![1]), make_cell(vec![0])];
+        let mut game = Game {
+            cells,
+            allied_bases: vec![0],
+            ennemy_bases: vec![1],
+            allied_score: 0,
+            ennemy_score: 0,
+            distances: None,
+            next_hop: None,
+            pheromone: vec![0.0; 2],
+            pathing_mode: PathMode::ShortestHop,
+        };
+
+        game.deposit_pheromone(&[0], &[5, 0]);
+        let after_harvest = game.pheromone_at(0);
+        assert_eq!(after_harvest, 5.0 * 0.85);
+
+        game.deposit_pheromone(&[], &[0, 0]);
+        let after_quiet_turn = game.pheromone_at(0);
+        assert_eq!(after_quiet_turn, after_harvest * 0.85);
+        assert!(after_quiet_turn < after_harvest);
+    }
+
+    /// A diamond: 0 and 2 are both reachable from each other in 2 hops, either via 1 or via
+    /// 3. Loading cell 1 with enemy ants should make `SafestRoute` prefer 3 even though both
+    /// routes are the same length under plain `ShortestHop`.
+    #[test]
+    fn safest_route_avoids_enemy_heavy_cells() {
+        let mut cells = vec![
+            make_cell(vec![1, 3]),
+            make_cell(vec![0, 2]),
+            make_cell(vec![1, 3]),
+            make_cell(vec![0, 2]),
+        ];
+        cells[1].ennemy_ants = 5;
+        let mut game = Game {
+            cells,
+            allied_bases: vec![0],
+            ennemy_bases: vec![2],
+            allied_score: 0,
+            ennemy_score: 0,
+            distances: None,
+            next_hop: None,
+            pheromone: vec![0.0; 4],
+            pathing_mode: PathMode::ShortestHop,
+        };
+        game.init_topology();
+
+        let shortest = game.path_with_mode(0, 2, PathMode::ShortestHop);
+        assert!(shortest.contains(&1));
+
+        game.set_pathing_mode(PathMode::SafestRoute);
+        let safest = game.path_with_mode(0, 2, game.pathing_mode);
+        assert_eq!(safest, vec![0, 3, 2]);
+    }
+
+    /// `MESSAGE` must keep its text verbatim, including a `;` and surrounding spaces that
+    /// would otherwise be eaten by the `;`-splitting and trimming done for other commands.
+    #[test]
+    fn message_keeps_its_remainder_verbatim() {
+        let actions = parse_actions("LINE 0 1 10;MESSAGE hello; world \n").unwrap();
+        assert_eq!(
+            actions,
+            vec![
+                Action::Line(ActionLine {
+                    source: 0,
+                    destination: 1,
+                    strength: 10,
+                }),
+                Action::ActionMessage(ActionMessage {
+                    message: "hello; world ".to_string(),
+                }),
+            ]
+        );
+    }
+}